@@ -44,6 +44,36 @@ struct SetRustVersionCmd {
     /// Channel to use latest version
     #[clap(long, default_value("stable"))]
     channel: String,
+    /// Set rust-version once in the root `[workspace.package]` table and make
+    /// each member inherit it with `rust-version.workspace = true`
+    #[clap(long)]
+    workspace_inherit: bool,
+    /// Report what would change without writing any manifest, exiting with a
+    /// non-zero status if any file would change
+    #[clap(long)]
+    check: bool,
+    /// Verify each manifest's rust-version is satisfiable by the rustc on PATH
+    /// instead of bumping, exiting non-zero if any MSRV exceeds the toolchain
+    #[clap(long)]
+    verify: bool,
+    /// Output format for the report
+    #[clap(long, arg_enum, default_value("human"))]
+    message_format: MessageFormat,
+    /// Pin the target this many minor versions behind the channel's latest
+    /// (e.g. latest `1.80` with `--relative 2` yields `1.78`)
+    #[clap(long)]
+    relative: Option<u64>,
+    /// Lowest minor version `--relative` may reach; the target never goes below
+    /// `1.0` regardless
+    #[clap(long, default_value("0"))]
+    relative_floor: u64,
+}
+
+#[derive(clap::ArgEnum, Clone, Copy, Debug, PartialEq, Eq)]
+enum MessageFormat {
+    Human,
+    Short,
+    Json,
 }
 
 #[derive(thiserror::Error, Debug)]
@@ -59,6 +89,10 @@ enum Error {
     WorkspaceMembersIsNotArray,
     #[error("parsing manifest workspace member is not string")]
     WorkspaceMemberIsNotString,
+    #[error("expanding workspace member glob")]
+    WorkspaceMemberGlob(#[from] glob::PatternError),
+    #[error("reading workspace member glob entry")]
+    WorkspaceMemberGlobEntry(#[from] glob::GlobError),
 
     #[error("making http request")]
     Http(#[from] ureq::Error),
@@ -80,12 +114,191 @@ enum Error {
 
     #[error("writing manifrst")]
     WritingManifest(io::Error),
+
+    #[error("invalid rust-version \"{0}\": expected major[.minor[.patch]]")]
+    RustVersionInvalid(String),
+    #[error("invalid rust-version \"{0}\": caret requirements are not allowed")]
+    RustVersionCaret(String),
+    #[error("invalid rust-version \"{0}\": pre-release identifiers are not allowed")]
+    RustVersionPrerelease(String),
+
+    #[error("serializing report")]
+    SerializingReport(#[from] serde_json::Error),
+
+    #[error("running rustc")]
+    RunningRustc(io::Error),
+    #[error("parsing rustc version")]
+    ParsingRustcVersion,
+    #[error("--message-format is not supported with --verify")]
+    MessageFormatWithVerify,
+}
+
+/// A `rust-version` field value, parsed with the same rules cargo enforces for
+/// a `PartialVersion`: a bare `major`, `major.minor`, or `major.minor.patch`,
+/// where the major component is required and the rest are optional. Caret
+/// requirements (`^1.43`) and pre-release identifiers (`1.43.0-beta.1`) are
+/// rejected.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct RustVersion {
+    major: u64,
+    minor: Option<u64>,
+    patch: Option<u64>,
+}
+
+impl std::str::FromStr for RustVersion {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.starts_with('^') {
+            return Err(Error::RustVersionCaret(s.to_owned()));
+        }
+        if s.contains('-') {
+            return Err(Error::RustVersionPrerelease(s.to_owned()));
+        }
+        if s.contains('+') {
+            return Err(Error::RustVersionInvalid(s.to_owned()));
+        }
+        let mut parts = s.split('.');
+        let invalid = || Error::RustVersionInvalid(s.to_owned());
+        let major = parts
+            .next()
+            .filter(|p| !p.is_empty())
+            .ok_or_else(invalid)?
+            .parse()
+            .map_err(|_| invalid())?;
+        let minor = parts.next().map(str::parse).transpose().map_err(|_| invalid())?;
+        let patch = parts.next().map(str::parse).transpose().map_err(|_| invalid())?;
+        if parts.next().is_some() {
+            return Err(invalid());
+        }
+        Ok(RustVersion {
+            major,
+            minor,
+            patch,
+        })
+    }
+}
+
+impl RustVersion {
+    /// The version as a `(major, minor, patch)` tuple, treating absent minor or
+    /// patch components as zero for ordering.
+    fn to_tuple(&self) -> (u64, u64, u64) {
+        (self.major, self.minor.unwrap_or(0), self.patch.unwrap_or(0))
+    }
+
+    /// Whether `rustc` satisfies this value interpreted as a cargo MSRV: a caret
+    /// requirement (`1.62` ⇒ `>=1.62, <2.0`). The major components must match
+    /// and the toolchain must be at least this version.
+    fn caret_matches(&self, rustc: &RustVersion) -> bool {
+        rustc.major == self.major && rustc.to_tuple() >= self.to_tuple()
+    }
+}
+
+impl std::fmt::Display for RustVersion {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.major)?;
+        if let Some(minor) = self.minor {
+            write!(f, ".{}", minor)?;
+        }
+        if let Some(patch) = self.patch {
+            write!(f, ".{}", patch)?;
+        }
+        Ok(())
+    }
+}
+
+/// A per-manifest outcome, buffered for the `json` report format.
+#[derive(serde::Serialize)]
+struct Record {
+    manifest: String,
+    current: Option<String>,
+    latest: String,
+    changed: bool,
+}
+
+/// Collects and emits the run's output in the chosen `MessageFormat`. The
+/// human-facing log lines of `run`/`run_for_manifest` feed into here so that
+/// `short` and `json` can suppress the chatter and emit machine-readable
+/// records instead.
+struct Reporter {
+    format: MessageFormat,
+    records: Vec<Record>,
+    changed: bool,
+}
+
+impl Reporter {
+    fn new(format: MessageFormat) -> Self {
+        Reporter {
+            format,
+            records: Vec::new(),
+            changed: false,
+        }
+    }
+
+    /// Emit a free-form progress line, shown only in the `human` format.
+    fn log(&self, line: impl std::fmt::Display) {
+        if self.format == MessageFormat::Human {
+            println!("{}", line);
+        }
+    }
+
+    /// Record the outcome for a single manifest.
+    fn record(&mut self, manifest: &str, current: Option<&str>, latest: &str, changed: bool) {
+        self.changed |= changed;
+        match self.format {
+            MessageFormat::Human => {
+                if changed {
+                    println!(
+                        "{}: updating rust-version: {} => {}",
+                        manifest,
+                        current.unwrap_or("None"),
+                        latest
+                    );
+                } else {
+                    println!(
+                        "{}: up-to-date rust-version: {}",
+                        manifest,
+                        current.unwrap_or("None")
+                    );
+                }
+            }
+            MessageFormat::Short => {
+                println!(
+                    "{} {} {} {}",
+                    manifest,
+                    current.unwrap_or("None"),
+                    latest,
+                    if changed { "changed" } else { "unchanged" }
+                );
+            }
+            MessageFormat::Json => self.records.push(Record {
+                manifest: manifest.to_owned(),
+                current: current.map(str::to_owned),
+                latest: latest.to_owned(),
+                changed,
+            }),
+        }
+    }
+
+    /// Flush any buffered records. For `json` this prints the accumulated array.
+    fn finish(&self) -> Result<(), Error> {
+        if self.format == MessageFormat::Json {
+            let json = serde_json::to_string(&self.records)?;
+            println!("{}", json);
+        }
+        Ok(())
+    }
 }
 
 impl SetRustVersionCmd {
     pub fn run(&self) -> Result<(), Error> {
+        if self.verify {
+            return self.run_verify();
+        }
+
+        let mut reporter = Reporter::new(self.message_format);
         // Collect latest rust-version.
-        println!("channel: {}", self.channel);
+        reporter.log(format!("channel: {}", self.channel));
         let latest_version = {
             let url = format!(
                 "https://static.rust-lang.org/dist/channel-rust-{}.toml",
@@ -105,25 +318,56 @@ impl SetRustVersionCmd {
                 .ok_or(Error::ReleaseInfoRustCVersionIsMissing)?
                 .as_str()
                 .ok_or(Error::ReleaseInfoRustCVersionIsNotString)?;
-            let version = version_and_meta
+            let version: RustVersion = version_and_meta
                 .split(' ')
                 .next()
-                .ok_or(Error::ReleaseInfoRustCVersionIsEmpty)?;
-            let major_minor_version = version.split('.').take(2).collect::<Vec<_>>().join(".");
-            major_minor_version
+                .ok_or(Error::ReleaseInfoRustCVersionIsEmpty)?
+                .parse()?;
+            RustVersion {
+                major: version.major,
+                minor: version.minor,
+                patch: None,
+            }
         };
-        println!("latest rust-version: {}", latest_version);
+        reporter.log(format!("latest rust-version: {}", latest_version));
 
-        self.run_for_manifest(&self.manifest, &latest_version)
+        // Optionally pin the target a fixed number of minor versions behind.
+        let target_version = match self.relative {
+            Some(n) => {
+                let minor = latest_version
+                    .minor
+                    .unwrap_or(0)
+                    .saturating_sub(n)
+                    .max(self.relative_floor);
+                let relative = RustVersion {
+                    major: latest_version.major,
+                    minor: Some(minor),
+                    patch: None,
+                };
+                reporter.log(format!("relative rust-version: {}", relative));
+                relative.to_string()
+            }
+            None => latest_version.to_string(),
+        };
+
+        self.run_for_manifest(&self.manifest, &target_version, &mut reporter)?;
+        reporter.finish()?;
+
+        // In check mode a pending change is a failure, surfaced via exit status.
+        if self.check && reporter.changed {
+            std::process::exit(1);
+        }
+        Ok(())
     }
 
     pub fn run_for_manifest(
         &self,
         manifest_path: impl AsRef<std::path::Path>,
         latest_version: &str,
+        reporter: &mut Reporter,
     ) -> Result<(), Error> {
         let manifest_path_str = manifest_path.as_ref().to_string_lossy();
-        println!("{}: reading", manifest_path_str);
+        reporter.log(format!("{}: reading", manifest_path_str));
         let manifest_raw = fs::read_to_string(&manifest_path).map_err(Error::ReadingManifest)?;
         let mut manifest = manifest_raw
             .parse::<toml_edit::Document>()
@@ -131,59 +375,323 @@ impl SetRustVersionCmd {
 
         // Check if workspace, and recursively load member manifests if so.
         if let Some(workspace) = manifest.get("workspace") {
-            println!("{}: found workspace", manifest_path_str);
+            reporter.log(format!("{}: found workspace", manifest_path_str));
             let workspace_path = manifest_path
                 .as_ref()
                 .parent()
                 .unwrap_or_else(|| manifest_path.as_ref());
-            let members = workspace
-                .get("members")
-                .ok_or(Error::WorkspaceMembersIsMissing)?
-                .as_array()
-                .ok_or(Error::WorkspaceMembersIsNotArray)?;
-            for m in members {
-                let m_path = workspace_path
-                    .join(m.as_str().ok_or(Error::WorkspaceMemberIsNotString)?)
-                    .join("Cargo.toml");
-                self.run_for_manifest(m_path, latest_version)?;
+            let members = workspace_members(workspace_path, workspace)?;
+            if self.workspace_inherit {
+                self.set_workspace_package(&manifest_path, &mut manifest, latest_version, reporter)?;
+                for m_path in members {
+                    self.set_member_inherit(m_path, latest_version, reporter)?;
+                }
+            } else {
+                for m_path in members {
+                    self.run_for_manifest(m_path, latest_version, reporter)?;
+                }
             }
             return Ok(());
         }
 
         // Collect current rust-version.
-        let current_version = manifest
+        let rust_version = manifest
             .get("package")
-            .and_then(|package| package.get("rust-version"))
-            .and_then(toml_edit::Item::as_str);
+            .and_then(|package| package.get("rust-version"));
 
-        // If current and latest are same, do nothing.
+        // Members that inherit rust-version from the workspace carry their value
+        // at the workspace root, so there is nothing to edit here.
+        if rust_version
+            .and_then(toml_edit::Item::as_table_like)
+            .and_then(|t| t.get("workspace"))
+            .and_then(toml_edit::Item::as_bool)
+            == Some(true)
+        {
+            reporter.log(format!(
+                "{}: inherits rust-version from workspace",
+                manifest_path_str
+            ));
+            return Ok(());
+        }
+
+        let current_version = rust_version.and_then(toml_edit::Item::as_str);
+
+        // Validate the existing value so a malformed rust-version is reported
+        // rather than silently overwritten.
         if let Some(current_version) = current_version {
-            if current_version == latest_version {
-                println!(
-                    "{}: up-to-date rust-version: {}",
-                    manifest_path_str, current_version
-                );
-                return Ok(());
-            }
+            current_version.parse::<RustVersion>()?;
+        }
+
+        let changed = current_version != Some(latest_version);
+        reporter.record(&manifest_path_str, current_version, latest_version, changed);
+
+        // Nothing to do if already up-to-date, or if only checking.
+        if !changed || self.check {
+            return Ok(());
         }
 
-        // Update rust-version to latest.
-        println!(
-            "{}: updating rust-version: {} => {}",
-            manifest_path_str,
-            current_version.unwrap_or("None"),
-            latest_version
-        );
         manifest["package"]["rust-version"] = toml_edit::value(latest_version);
-        fs::OpenOptions::new()
-            .write(true)
-            .open(&manifest_path)
-            .map_err(Error::WritingManifest)?
-            .write_all(manifest.to_string().as_bytes())
-            .map_err(Error::WritingManifest)?;
+        write_manifest(&manifest_path, &manifest)?;
 
         Ok(())
     }
+
+    /// Verify each manifest's declared rust-version against the installed rustc.
+    fn run_verify(&self) -> Result<(), Error> {
+        // Verify reports compatibility, not per-manifest version records, so the
+        // machine-readable formats do not apply here.
+        if self.message_format != MessageFormat::Human {
+            return Err(Error::MessageFormatWithVerify);
+        }
+        let mut reporter = Reporter::new(self.message_format);
+        let rustc = Self::installed_rustc()?;
+        reporter.log(format!("rustc: {}", rustc));
+        let incompatible = self.verify_for_manifest(&self.manifest, &rustc, None, &mut reporter)?;
+        reporter.finish()?;
+        if incompatible {
+            std::process::exit(1);
+        }
+        Ok(())
+    }
+
+    /// Parse the version of the `rustc` on PATH, stripping any pre-release
+    /// identifier (e.g. `1.80.0-nightly`) before matching.
+    fn installed_rustc() -> Result<RustVersion, Error> {
+        let output = std::process::Command::new("rustc")
+            .arg("--version")
+            .output()
+            .map_err(Error::RunningRustc)?;
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        let token = stdout
+            .split_whitespace()
+            .nth(1)
+            .ok_or(Error::ParsingRustcVersion)?;
+        let version = token.split('-').next().ok_or(Error::ParsingRustcVersion)?;
+        version.parse()
+    }
+
+    /// Verify a single manifest, recursing into workspace members. Returns
+    /// `true` if any package's MSRV exceeds the installed toolchain. `inherited`
+    /// carries the workspace root's `rust-version` for members that inherit it.
+    fn verify_for_manifest(
+        &self,
+        manifest_path: impl AsRef<std::path::Path>,
+        rustc: &RustVersion,
+        inherited: Option<&RustVersion>,
+        reporter: &mut Reporter,
+    ) -> Result<bool, Error> {
+        let manifest_path_str = manifest_path.as_ref().to_string_lossy();
+        reporter.log(format!("{}: reading", manifest_path_str));
+        let manifest_raw = fs::read_to_string(&manifest_path).map_err(Error::ReadingManifest)?;
+        let manifest = manifest_raw
+            .parse::<toml_edit::Document>()
+            .map_err(Error::ParsingManifest)?;
+
+        if let Some(workspace) = manifest.get("workspace") {
+            reporter.log(format!("{}: found workspace", manifest_path_str));
+            let workspace_path = manifest_path
+                .as_ref()
+                .parent()
+                .unwrap_or_else(|| manifest_path.as_ref());
+            let inherited = workspace
+                .get("package")
+                .and_then(|package| package.get("rust-version"))
+                .and_then(toml_edit::Item::as_str)
+                .map(str::parse)
+                .transpose()?;
+            let mut incompatible = false;
+            for m_path in workspace_members(workspace_path, workspace)? {
+                incompatible |=
+                    self.verify_for_manifest(m_path, rustc, inherited.as_ref(), reporter)?;
+            }
+            return Ok(incompatible);
+        }
+
+        let rust_version = manifest
+            .get("package")
+            .and_then(|package| package.get("rust-version"));
+
+        // Resolve the effective rust-version, following workspace inheritance.
+        let inherits = rust_version
+            .and_then(toml_edit::Item::as_table_like)
+            .and_then(|t| t.get("workspace"))
+            .and_then(toml_edit::Item::as_bool)
+            == Some(true);
+        let msrv = if inherits {
+            inherited.cloned()
+        } else {
+            rust_version
+                .and_then(toml_edit::Item::as_str)
+                .map(str::parse)
+                .transpose()?
+        };
+
+        let msrv = match msrv {
+            Some(msrv) => msrv,
+            None => {
+                reporter.log(format!("{}: no rust-version", manifest_path_str));
+                return Ok(false);
+            }
+        };
+
+        if msrv.caret_matches(rustc) {
+            reporter.log(format!(
+                "{}: rust-version {} compatible with rustc {}",
+                manifest_path_str, msrv, rustc
+            ));
+            Ok(false)
+        } else {
+            println!(
+                "{}: rust-version {} incompatible with rustc {}",
+                manifest_path_str, msrv, rustc
+            );
+            Ok(true)
+        }
+    }
+
+    /// Set `rust-version` in the root `[workspace.package]` table, creating the
+    /// table if it does not exist, so that inheriting members pick it up.
+    fn set_workspace_package(
+        &self,
+        manifest_path: impl AsRef<std::path::Path>,
+        manifest: &mut toml_edit::Document,
+        latest_version: &str,
+        reporter: &mut Reporter,
+    ) -> Result<(), Error> {
+        let manifest_path_str = manifest_path.as_ref().to_string_lossy();
+        let current_version = manifest
+            .get("workspace")
+            .and_then(|workspace| workspace.get("package"))
+            .and_then(|package| package.get("rust-version"))
+            .and_then(toml_edit::Item::as_str);
+        let changed = current_version != Some(latest_version);
+        reporter.record(&manifest_path_str, current_version, latest_version, changed);
+        if !changed || self.check {
+            return Ok(());
+        }
+        manifest["workspace"]["package"]["rust-version"] = toml_edit::value(latest_version);
+        write_manifest(&manifest_path, manifest)
+    }
+
+    /// Rewrite a member manifest's `package.rust-version` to inherit from the
+    /// workspace, leaving members that already inherit untouched. The member's
+    /// effective rust-version becomes the workspace `latest_version`.
+    fn set_member_inherit(
+        &self,
+        manifest_path: impl AsRef<std::path::Path>,
+        latest_version: &str,
+        reporter: &mut Reporter,
+    ) -> Result<(), Error> {
+        let manifest_path_str = manifest_path.as_ref().to_string_lossy();
+        reporter.log(format!("{}: reading", manifest_path_str));
+        let manifest_raw = fs::read_to_string(&manifest_path).map_err(Error::ReadingManifest)?;
+        let mut manifest = manifest_raw
+            .parse::<toml_edit::Document>()
+            .map_err(Error::ParsingManifest)?;
+
+        let rust_version = manifest
+            .get("package")
+            .and_then(|package| package.get("rust-version"));
+        let already_inherits = rust_version
+            .and_then(toml_edit::Item::as_table_like)
+            .and_then(|t| t.get("workspace"))
+            .and_then(toml_edit::Item::as_bool)
+            == Some(true);
+        if already_inherits {
+            reporter.log(format!("{}: already inherits rust-version", manifest_path_str));
+            return Ok(());
+        }
+
+        let current_version = rust_version.and_then(toml_edit::Item::as_str);
+        reporter.record(&manifest_path_str, current_version, latest_version, true);
+        if self.check {
+            return Ok(());
+        }
+        let mut inherit = toml_edit::InlineTable::new();
+        inherit.insert("workspace", true.into());
+        manifest["package"]["rust-version"] = toml_edit::value(inherit);
+        write_manifest(&manifest_path, &manifest)
+    }
+}
+
+/// Write a parsed manifest back to disk, preserving formatting and comments.
+fn write_manifest(
+    manifest_path: impl AsRef<std::path::Path>,
+    manifest: &toml_edit::Document,
+) -> Result<(), Error> {
+    fs::OpenOptions::new()
+        .write(true)
+        .truncate(true)
+        .open(&manifest_path)
+        .map_err(Error::WritingManifest)?
+        .write_all(manifest.to_string().as_bytes())
+        .map_err(Error::WritingManifest)?;
+    Ok(())
+}
+
+/// Enumerate the member manifests of a workspace.
+///
+/// Each entry in `members`, `default-members`, and `exclude` is treated as a
+/// glob relative to the workspace root, expanding to any directory that
+/// contains a `Cargo.toml`. `exclude` entries are subtracted from the set. If
+/// `members` is absent but `default-members` is present the latter is used, so
+/// `WorkspaceMembersIsMissing` is only an error when neither is declared.
+fn workspace_members(
+    workspace_path: &std::path::Path,
+    workspace: &toml_edit::Item,
+) -> Result<Vec<std::path::PathBuf>, Error> {
+    let members = workspace_member_dirs(workspace_path, workspace.get("members"))?;
+    let members = match members {
+        Some(members) => members,
+        None => workspace_member_dirs(workspace_path, workspace.get("default-members"))?
+            .ok_or(Error::WorkspaceMembersIsMissing)?,
+    };
+    let exclude = workspace_member_dirs(workspace_path, workspace.get("exclude"))?
+        .unwrap_or_default();
+
+    let mut paths = Vec::new();
+    for dir in members {
+        if exclude.contains(&dir) {
+            continue;
+        }
+        paths.push(dir.join("Cargo.toml"));
+    }
+    Ok(paths)
+}
+
+/// Expand a workspace member list (`members`, `default-members`, or `exclude`)
+/// into the set of directories it selects, treating each entry as a glob
+/// relative to the workspace root. Returns `None` when the list is absent.
+fn workspace_member_dirs(
+    workspace_path: &std::path::Path,
+    list: Option<&toml_edit::Item>,
+) -> Result<Option<Vec<std::path::PathBuf>>, Error> {
+    let list = match list {
+        Some(list) => list.as_array().ok_or(Error::WorkspaceMembersIsNotArray)?,
+        None => return Ok(None),
+    };
+    // Escape the workspace root so that glob metacharacters in the checkout
+    // path are matched literally; only the member `entry` is a glob pattern.
+    let base = glob::Pattern::escape(&workspace_path.to_string_lossy());
+    let mut dirs = Vec::new();
+    for entry in list {
+        let entry = entry.as_str().ok_or(Error::WorkspaceMemberIsNotString)?;
+        // An empty workspace root (a manifest path with no directory part)
+        // means glob the entry directly; otherwise anchor the entry to the
+        // escaped root.
+        let pattern = if base.is_empty() {
+            entry.to_owned()
+        } else {
+            format!("{}{}{}", base, std::path::MAIN_SEPARATOR, entry)
+        };
+        for path in glob::glob(&pattern)? {
+            let path = path?;
+            if path.join("Cargo.toml").is_file() && !dirs.contains(&path) {
+                dirs.push(path);
+            }
+        }
+    }
+    Ok(Some(dirs))
 }
 
 fn main() {
@@ -0,0 +1,33 @@
+use assert_cmd::prelude::*;
+use assert_fs::prelude::*;
+use predicates::prelude::*;
+use std::process::Command;
+
+#[test]
+fn invalid_rust_version() -> Result<(), Box<dyn std::error::Error>> {
+    let manifest = assert_fs::NamedTempFile::new("Cargo.toml")?;
+    manifest.write_str(
+        r#"
+[package]
+rust-version = "^1.43"
+"#,
+    )?;
+
+    let mut cmd = Command::cargo_bin("cargo-set-rust-version")?;
+    cmd.arg("set-rust-version");
+    cmd.arg("--manifest").arg(manifest.path());
+    cmd.arg("--channel").arg("1.62");
+    cmd.assert().stderr(predicate::str::contains(
+        "invalid rust-version \"^1.43\": caret requirements are not allowed",
+    ));
+
+    // The malformed value is left untouched rather than overwritten.
+    manifest.assert(
+        r#"
+[package]
+rust-version = "^1.43"
+"#,
+    );
+
+    Ok(())
+}
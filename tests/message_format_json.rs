@@ -0,0 +1,34 @@
+use assert_cmd::prelude::*;
+use assert_fs::prelude::*;
+use std::process::Command;
+
+#[test]
+fn message_format_json() -> Result<(), Box<dyn std::error::Error>> {
+    let manifest = assert_fs::NamedTempFile::new("Cargo.toml")?;
+    manifest.write_str(
+        r#"
+[package]
+rust-version = "1.60"
+"#,
+    )?;
+
+    let mut cmd = Command::cargo_bin("cargo-set-rust-version")?;
+    cmd.arg("set-rust-version");
+    cmd.arg("--manifest").arg(manifest.path());
+    cmd.arg("--channel").arg("1.62");
+    cmd.arg("--message-format").arg("json");
+    // The json format suppresses the human chatter and emits one array.
+    cmd.assert().success().stdout(format!(
+        "[{{\"manifest\":\"{0}\",\"current\":\"1.60\",\"latest\":\"1.62\",\"changed\":true}}]\n",
+        manifest.path().to_string_lossy()
+    ));
+
+    manifest.assert(
+        r#"
+[package]
+rust-version = "1.62"
+"#,
+    );
+
+    Ok(())
+}
@@ -0,0 +1,33 @@
+use assert_cmd::prelude::*;
+use assert_fs::prelude::*;
+use predicates::prelude::*;
+use std::process::Command;
+
+#[test]
+fn verify() -> Result<(), Box<dyn std::error::Error>> {
+    let manifest = assert_fs::NamedTempFile::new("Cargo.toml")?;
+    manifest.write_str(
+        r#"
+[package]
+rust-version = "1.0"
+"#,
+    )?;
+
+    let mut cmd = Command::cargo_bin("cargo-set-rust-version")?;
+    cmd.arg("set-rust-version");
+    cmd.arg("--manifest").arg(manifest.path());
+    cmd.arg("--verify");
+    cmd.assert().success().stdout(predicate::str::contains(
+        format!("{}: rust-version 1.0 compatible with rustc", manifest.path().to_string_lossy()),
+    ));
+
+    // Verify never mutates the manifest.
+    manifest.assert(
+        r#"
+[package]
+rust-version = "1.0"
+"#,
+    );
+
+    Ok(())
+}
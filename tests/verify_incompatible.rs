@@ -0,0 +1,26 @@
+use assert_cmd::prelude::*;
+use assert_fs::prelude::*;
+use predicates::prelude::*;
+use std::process::Command;
+
+#[test]
+fn verify_incompatible() -> Result<(), Box<dyn std::error::Error>> {
+    let manifest = assert_fs::NamedTempFile::new("Cargo.toml")?;
+    manifest.write_str(
+        r#"
+[package]
+rust-version = "99.0"
+"#,
+    )?;
+
+    let mut cmd = Command::cargo_bin("cargo-set-rust-version")?;
+    cmd.arg("set-rust-version");
+    cmd.arg("--manifest").arg(manifest.path());
+    cmd.arg("--verify");
+    // An MSRV beyond the installed toolchain exits non-zero.
+    cmd.assert().failure().stdout(predicate::str::contains(
+        format!("{}: rust-version 99.0 incompatible with rustc", manifest.path().to_string_lossy()),
+    ));
+
+    Ok(())
+}
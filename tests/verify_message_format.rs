@@ -0,0 +1,28 @@
+use assert_cmd::prelude::*;
+use assert_fs::prelude::*;
+use predicates::prelude::*;
+use std::process::Command;
+
+#[test]
+fn verify_message_format() -> Result<(), Box<dyn std::error::Error>> {
+    let manifest = assert_fs::NamedTempFile::new("Cargo.toml")?;
+    manifest.write_str(
+        r#"
+[package]
+rust-version = "1.0"
+"#,
+    )?;
+
+    let mut cmd = Command::cargo_bin("cargo-set-rust-version")?;
+    cmd.arg("set-rust-version");
+    cmd.arg("--manifest").arg(manifest.path());
+    cmd.arg("--verify");
+    cmd.arg("--message-format").arg("json");
+    // Machine-readable formats do not apply to verify and are rejected rather
+    // than emitting a misleading empty report.
+    cmd.assert().stderr(predicate::str::contains(
+        "--message-format is not supported with --verify",
+    ));
+
+    Ok(())
+}
@@ -0,0 +1,90 @@
+use assert_cmd::prelude::*;
+use assert_fs::prelude::*;
+use std::process::Command;
+
+#[test]
+fn workspace_inherit() -> Result<(), Box<dyn std::error::Error>> {
+    let temp = assert_fs::TempDir::new()?;
+    let manifest = temp.child("Cargo.toml");
+    manifest.write_str(
+        r#"
+[workspace]
+members = ["a", "b", "c"]
+"#,
+    )?;
+    let manifest_a = temp.child("a/Cargo.toml");
+    manifest_a.write_str(
+        r#"
+[package]
+# Pin our MSRV.
+rust-version = "1.60"
+"#,
+    )?;
+    let manifest_b = temp.child("b/Cargo.toml");
+    manifest_b.write_str(
+        r#"
+[package]
+rust-version.workspace = true
+"#,
+    )?;
+    let manifest_c = temp.child("c/Cargo.toml");
+    manifest_c.write_str(
+        r#"
+[package]
+rust-version = "1.59"
+"#,
+    )?;
+
+    let mut cmd = Command::cargo_bin("cargo-set-rust-version")?;
+    cmd.arg("set-rust-version");
+    cmd.arg("--manifest").arg(manifest.path());
+    cmd.arg("--channel").arg("1.62");
+    cmd.arg("--workspace-inherit");
+    cmd.assert().success().stdout(format!(
+        "channel: 1.62
+latest rust-version: 1.62
+{0}: reading
+{0}: found workspace
+{0}: updating rust-version: None => 1.62
+{1}: reading
+{1}: updating rust-version: 1.60 => 1.62
+{2}: reading
+{2}: already inherits rust-version
+{3}: reading
+{3}: updating rust-version: 1.59 => 1.62
+",
+        manifest.path().to_string_lossy(),
+        manifest_a.path().to_string_lossy(),
+        manifest_b.path().to_string_lossy(),
+        manifest_c.path().to_string_lossy(),
+    ));
+
+    // The root manifest now centralizes rust-version.
+    let root = std::fs::read_to_string(manifest.path())?;
+    assert!(root.contains("[workspace.package]"));
+    assert!(root.contains("rust-version = \"1.62\""));
+
+    // Members inherit instead of carrying their own value, and comments around
+    // the rewritten field are preserved.
+    manifest_a.assert(
+        r#"
+[package]
+# Pin our MSRV.
+rust-version = { workspace = true }
+"#,
+    );
+    manifest_b.assert(
+        r#"
+[package]
+rust-version.workspace = true
+"#,
+    );
+    manifest_c.assert(
+        r#"
+[package]
+rust-version = { workspace = true }
+"#,
+    );
+
+    Ok(())
+}
@@ -0,0 +1,61 @@
+use assert_cmd::prelude::*;
+use assert_fs::prelude::*;
+use std::process::Command;
+
+#[test]
+fn workspace_relative() -> Result<(), Box<dyn std::error::Error>> {
+    let temp = assert_fs::TempDir::new()?;
+    let manifest = temp.child("Cargo.toml");
+    manifest.write_str(
+        r#"
+[workspace]
+members = ["a", "b"]
+"#,
+    )?;
+    let manifest_a = temp.child("a/Cargo.toml");
+    manifest_a.write_str(
+        r#"
+[package]
+rust-version = "1.60"
+"#,
+    )?;
+    let manifest_b = temp.child("b/Cargo.toml");
+    manifest_b.write_str(
+        r#"
+[package]
+rust-version = "1.62"
+"#,
+    )?;
+
+    let mut cmd = Command::cargo_bin("cargo-set-rust-version")?;
+    cmd.current_dir(temp.path());
+    cmd.arg("set-rust-version");
+    cmd.arg("--manifest").arg("Cargo.toml");
+    cmd.arg("--channel").arg("1.62");
+    cmd.assert().success().stdout(
+        "channel: 1.62
+latest rust-version: 1.62
+Cargo.toml: reading
+Cargo.toml: found workspace
+a/Cargo.toml: reading
+a/Cargo.toml: updating rust-version: 1.60 => 1.62
+b/Cargo.toml: reading
+b/Cargo.toml: up-to-date rust-version: 1.62
+",
+    );
+
+    manifest_a.assert(
+        r#"
+[package]
+rust-version = "1.62"
+"#,
+    );
+    manifest_b.assert(
+        r#"
+[package]
+rust-version = "1.62"
+"#,
+    );
+
+    Ok(())
+}